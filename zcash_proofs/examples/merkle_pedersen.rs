@@ -6,42 +6,10 @@ use rand_xorshift::XorShiftRng;
 use std::time::{Duration, Instant};
 use zcash_primitives::jubjub::{edwards, fs, JubjubBls12};
 use zcash_primitives::primitives::{Diversifier, ProofGenerationKey, ValueCommitment};
+use std::fs::File;
+use std::io::BufReader;
 use zcash_proofs::circuit::merkle::MerklePedersen;
-
-fn crs_size(parameters: &Parameters<Bls12>) -> (usize, usize) {
-
-    let mut vk_accum = 0;
-    // Groth16 vk
-    vk_accum += 48;
-    vk_accum += 96;
-    vk_accum += 96;
-    vk_accum += 96;
-    for g in &parameters.vk.ic {
-        vk_accum += 48;
-    }
-
-    let mut pk_accum = 0;
-    pk_accum += 48;
-    pk_accum += 48;
-    pk_accum += 48;
-    for g in &*parameters.a {
-        pk_accum += 48;
-    }
-    for g in &*parameters.b_g1 {
-        pk_accum += 48;
-    }
-    for g in &*parameters.b_g2 {
-        pk_accum += 96;
-    }
-    for g in &*parameters.h{
-        pk_accum += 48;
-    }
-    for g in &*parameters.l{
-        pk_accum += 48;
-    }
-
-    (vk_accum, pk_accum)
-}
+use zcash_proofs::params;
 
 fn main() {
     let jubjub_params = &JubjubBls12::new();
@@ -66,7 +34,17 @@ fn main() {
             rng,
         )
         .unwrap();
-    println!("crs size: {:?}", crs_size(&groth_params));
+
+        let (vk_bytes, pk_bytes) = params::serialized_size(&groth_params);
+        println!(
+            "depth {}: CRS size: {} bytes (vk), {} bytes (pk)",
+            TREE_DEPTH, vk_bytes, pk_bytes
+        );
+
+        let params_path = format!("merkle_pedersen-{}.params", TREE_DEPTH);
+        params::write(&groth_params, File::create(&params_path).unwrap()).unwrap();
+        let groth_params =
+            params::read(BufReader::new(File::open(&params_path).unwrap())).unwrap();
 
         const SAMPLES: u32 = 50;
 
@@ -3,7 +3,9 @@
 pub mod ecc;
 pub mod pedersen_hash;
 
+pub mod circuit_digest;
 pub mod sapling;
 pub mod sprout;
 pub mod merkle;
+pub mod merkle_hash;
 pub mod merkle_sha;
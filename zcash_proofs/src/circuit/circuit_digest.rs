@@ -0,0 +1,166 @@
+//! A deterministic digest of a circuit's constraint system.
+//!
+//! `generate_random_parameters` regenerates the CRS from scratch, with
+//! no guarantee that the circuit shape it was built from matches the
+//! one a prover later synthesizes against. `circuit_digest` folds every
+//! allocated variable and every enforced constraint into a single
+//! Blake2b digest, independent of namespace strings, so that a digest
+//! recorded alongside a `Parameters` file can be checked against the
+//! circuit in use before proving.
+
+use bellman::{Circuit, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable};
+use blake2b_simd::{Params, State};
+use ff::{Field, PrimeField, PrimeFieldRepr};
+use pairing::bls12_381::Bls12;
+use pairing::Engine;
+use std::marker::PhantomData;
+
+/// A `ConstraintSystem` that performs no constraint satisfaction
+/// checking; it only counts allocated variables and absorbs every
+/// enforced constraint into a running Blake2b state.
+struct DigestCS<E: Engine> {
+    state: State,
+    num_inputs: usize,
+    num_aux: usize,
+    _marker: PhantomData<E>,
+}
+
+impl<E: Engine> DigestCS<E> {
+    fn new() -> Self {
+        DigestCS {
+            state: Params::new().hash_length(32).to_state(),
+            num_inputs: 1, // CS::one()
+            num_aux: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Canonically serializes `lc` as its sorted `(kind, variable_index,
+    /// coefficient)` triples and absorbs them into the digest.
+    ///
+    /// `Input` and `Aux` are tagged and sorted separately, rather than
+    /// folded into one another by summing `num_inputs` into the aux
+    /// index: `num_inputs` is live mutable state that keeps changing as
+    /// more inputs are allocated mid-synthesis (e.g. `pack_into_inputs`
+    /// or `AllocatedNum::inputize` allocate an input and immediately
+    /// `enforce` a constraint against earlier aux variables), so an aux
+    /// variable's canonical id would otherwise depend on how many inputs
+    /// happened to be allocated before the constraint that references
+    /// it -- letting unrelated variables collide onto the same index, or
+    /// the same variable hash differently in different constraints.
+    fn absorb_lc(&mut self, lc: &LinearCombination<E>) {
+        let mut terms: Vec<((u8, u64), Vec<u8>)> = lc
+            .as_ref()
+            .iter()
+            .map(|(var, coeff)| {
+                let index = match var.get_unchecked() {
+                    Index::Input(i) => (0u8, i as u64),
+                    Index::Aux(i) => (1u8, i as u64),
+                };
+
+                let mut repr = coeff.into_repr();
+                let mut bytes = vec![];
+                repr.write_le(&mut bytes).expect("writing to a Vec cannot fail");
+
+                (index, bytes)
+            })
+            .collect();
+
+        terms.sort_by(|a, b| a.0.cmp(&b.0));
+
+        self.state.update(&(terms.len() as u64).to_le_bytes());
+        for ((kind, index), coeff) in terms {
+            self.state.update(&[kind]);
+            self.state.update(&index.to_le_bytes());
+            self.state.update(&coeff);
+        }
+    }
+}
+
+impl<E: Engine> ConstraintSystem<E> for DigestCS<E> {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(&mut self, _annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        // Like bellman's own setup-phase `KeypairAssembly`, we don't care
+        // about the witness value here, only the shape of the circuit --
+        // ignore the closure's result so digesting a circuit synthesized
+        // with placeholder `None` witnesses (as `generate_random_parameters`
+        // does) doesn't fail on `SynthesisError::AssignmentMissing`.
+        let _ = f();
+
+        let var = Variable::new_unchecked(Index::Aux(self.num_aux));
+        self.num_aux += 1;
+        Ok(var)
+    }
+
+    fn alloc_input<F, A, AR>(&mut self, _annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let _ = f();
+
+        let var = Variable::new_unchecked(Index::Input(self.num_inputs));
+        self.num_inputs += 1;
+        Ok(var)
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, _annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+        LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+        LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+    {
+        let a = a(LinearCombination::zero());
+        let b = b(LinearCombination::zero());
+        let c = c(LinearCombination::zero());
+
+        self.absorb_lc(&a);
+        self.absorb_lc(&b);
+        self.absorb_lc(&c);
+    }
+
+    fn push_namespace<NR, N>(&mut self, _name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        // The digest must be independent of namespace strings, so we
+        // deliberately don't fold them in here.
+    }
+
+    fn pop_namespace(&mut self) {}
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+/// Hashes the constraint system synthesized by `circuit`, so that
+/// callers can verify it is byte-for-byte the one used during
+/// `generate_random_parameters`.
+pub fn circuit_digest<C: Circuit<Bls12>>(circuit: C) -> [u8; 32] {
+    let mut cs = DigestCS::<Bls12>::new();
+
+    circuit
+        .synthesize(&mut cs)
+        .expect("circuit must synthesize to be digested");
+
+    // Fold in the variable counts too, so that an allocated variable no
+    // constraint references still changes the digest.
+    cs.state.update(&(cs.num_inputs as u64).to_le_bytes());
+    cs.state.update(&(cs.num_aux as u64).to_le_bytes());
+
+    let hash = cs.state.finalize();
+    let mut digest = [0u8; 32];
+    digest.copy_from_slice(hash.as_bytes());
+    digest
+}
@@ -11,15 +11,73 @@ use zcash_primitives::constants;
 use zcash_primitives::primitives::{PaymentAddress, ProofGenerationKey, ValueCommitment};
 
 use super::ecc;
+use super::merkle_hash::{MerkleHash, MerklePath};
 use super::pedersen_hash;
 use bellman::gadgets::blake2s;
-use bellman::gadgets::boolean;
+use bellman::gadgets::boolean::Boolean;
 use bellman::gadgets::multipack;
 use bellman::gadgets::num;
 use bellman::gadgets::Assignment;
 
 pub const TREE_DEPTH: usize = zcash_primitives::sapling::SAPLING_COMMITMENT_TREE_DEPTH;
 
+/// The `MerkleHash` impl for the Sapling Pedersen-hash tree: nodes are
+/// field elements, and each layer's compression is a Pedersen hash of
+/// the (conditionally swapped) pair of children.
+pub struct PedersenMerkleHash<'a, E: JubjubEngine> {
+    pub params: &'a E::Params,
+}
+
+impl<'a, E: JubjubEngine> MerkleHash<E> for PedersenMerkleHash<'a, E> {
+    type Node = num::AllocatedNum<E>;
+    type Repr = E::Fr;
+
+    fn alloc<CS>(&self, cs: CS, value: Option<Self::Repr>) -> Result<Self::Node, SynthesisError>
+    where
+        CS: ConstraintSystem<E>,
+    {
+        num::AllocatedNum::alloc(cs, || Ok(value.get()?.clone()))
+    }
+
+    fn hash_layer<CS>(
+        &self,
+        mut cs: CS,
+        depth: usize,
+        cur: Self::Node,
+        sibling: Self::Node,
+        cur_is_right: &Boolean,
+    ) -> Result<Self::Node, SynthesisError>
+    where
+        CS: ConstraintSystem<E>,
+    {
+        // Swap the two if the current subtree is on the right
+        let (xl, xr) = num::AllocatedNum::conditionally_reverse(
+            cs.namespace(|| "conditional reversal of preimage"),
+            &cur,
+            &sibling,
+            cur_is_right,
+        )?;
+
+        // We don't need to be strict, because the function is
+        // collision-resistant. If the prover witnesses a congruency,
+        // they will be unable to find an authentication path in the
+        // tree with high probability.
+        let mut preimage = vec![];
+        preimage.extend(xl.to_bits_le(cs.namespace(|| "xl into bits"))?);
+        preimage.extend(xr.to_bits_le(cs.namespace(|| "xr into bits"))?);
+
+        // Compute the new subtree value
+        Ok(pedersen_hash::pedersen_hash(
+            cs.namespace(|| "computation of pedersen hash"),
+            pedersen_hash::Personalization::MerkleTree(depth),
+            &preimage,
+            self.params,
+        )?
+        .get_x()
+        .clone()) // Injective encoding
+    }
+}
+
 /// This is an instance of the `Spend` circuit.
 pub struct MerklePedersen<'a, E: JubjubEngine> {
     pub params: &'a E::Params,
@@ -40,60 +98,18 @@ impl<'a, E: JubjubEngine> Circuit<E> for MerklePedersen<'a, E> {
 
         let cm = num::AllocatedNum::alloc(cs.namespace(|| "leaf"), || Ok(self.leaf.get()?.clone()))?;
 
-        // This will store (least significant bit first)
-        // the position of the note in the tree, for use
-        // in nullifier computation.
-        let mut position_bits = vec![];
-
-        // This is an injective encoding, as cur is a
-        // point in the prime order subgroup.
-        let mut cur = cm.clone();
-
-        // Ascend the merkle tree authentication path
-        for (i, e) in self.auth_path.into_iter().enumerate() {
-            let cs = &mut cs.namespace(|| format!("merkle tree hash {}", i));
-
-            // Determines if the current subtree is the "right" leaf at this
-            // depth of the tree.
-            let cur_is_right = boolean::Boolean::from(boolean::AllocatedBit::alloc(
-                cs.namespace(|| "position bit"),
-                e.map(|e| e.1),
-            )?);
-
-            // Push this boolean for nullifier computation later
-            position_bits.push(cur_is_right.clone());
-
-            // Witness the authentication path element adjacent
-            // at this depth.
-            let path_element =
-                num::AllocatedNum::alloc(cs.namespace(|| "path element"), || Ok(e.get()?.0))?;
-
-            // Swap the two if the current subtree is on the right
-            let (xl, xr) = num::AllocatedNum::conditionally_reverse(
-                cs.namespace(|| "conditional reversal of preimage"),
-                &cur,
-                &path_element,
-                &cur_is_right,
-            )?;
-
-            // We don't need to be strict, because the function is
-            // collision-resistant. If the prover witnesses a congruency,
-            // they will be unable to find an authentication path in the
-            // tree with high probability.
-            let mut preimage = vec![];
-            preimage.extend(xl.to_bits_le(cs.namespace(|| "xl into bits"))?);
-            preimage.extend(xr.to_bits_le(cs.namespace(|| "xr into bits"))?);
-
-            // Compute the new subtree value
-            cur = pedersen_hash::pedersen_hash(
-                cs.namespace(|| "computation of pedersen hash"),
-                pedersen_hash::Personalization::MerkleTree(i),
-                &preimage,
-                self.params,
-            )?
-            .get_x()
-            .clone(); // Injective encoding
-        }
+        // Ascend the merkle tree authentication path. This is an
+        // injective encoding, as `cur` is a point in the prime order
+        // subgroup.
+        let hash = PedersenMerkleHash {
+            params: self.params,
+        };
+        let cur = MerklePath::compute_root(
+            cs.namespace(|| "merkle path"),
+            &hash,
+            cm,
+            self.auth_path,
+        )?;
 
         {
             let real_anchor_value = self.anchor;
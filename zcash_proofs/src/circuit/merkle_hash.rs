@@ -0,0 +1,80 @@
+//! A hash-function-generic Merkle authentication path gadget.
+//!
+//! [`circuit::merkle_sha`](super::merkle_sha) and
+//! [`circuit::merkle`](super::merkle) both walk an authentication path
+//! one layer at a time, conditionally swapping the current node with
+//! its sibling and hashing the pair -- they differ only in how a tree
+//! node is represented (SHA256's `[u8; 32]`/`Vec<Boolean>` vs.
+//! Pedersen's field elements) and in the compression function itself.
+//! `MerkleHash` factors that difference out, so a new in-circuit hash
+//! is a single trait impl rather than a whole new module. Trees are
+//! binary only: each layer combines one node with one sibling under a
+//! single position bit, and other arities are not supported.
+
+use bellman::gadgets::boolean::{AllocatedBit, Boolean};
+use bellman::{ConstraintSystem, SynthesisError};
+use pairing::Engine;
+
+/// The per-layer compression used by an in-circuit Merkle tree.
+pub trait MerkleHash<E: Engine> {
+    /// The in-circuit representation of a tree node (leaf or internal).
+    type Node: Clone;
+    /// The out-of-circuit representation of a node, as witnessed from
+    /// an authentication path.
+    type Repr: Clone;
+
+    /// Witnesses a node from its out-of-circuit representation.
+    fn alloc<CS>(&self, cs: CS, value: Option<Self::Repr>) -> Result<Self::Node, SynthesisError>
+    where
+        CS: ConstraintSystem<E>;
+
+    /// Conditionally swaps `cur` and `sibling` (if `cur_is_right`) and
+    /// hashes the pair to produce the parent node at `depth`.
+    fn hash_layer<CS>(
+        &self,
+        cs: CS,
+        depth: usize,
+        cur: Self::Node,
+        sibling: Self::Node,
+        cur_is_right: &Boolean,
+    ) -> Result<Self::Node, SynthesisError>
+    where
+        CS: ConstraintSystem<E>;
+}
+
+/// A Merkle authentication path gadget, generic over the hash `H` used
+/// to combine each layer.
+pub struct MerklePath;
+
+impl MerklePath {
+    /// Ascends the tree from `leaf`, witnessing each layer's position
+    /// bit and sibling from `auth_path`, and returns the computed root.
+    pub fn compute_root<E, CS, H>(
+        mut cs: CS,
+        hash: &H,
+        leaf: H::Node,
+        auth_path: Vec<Option<(H::Repr, bool)>>,
+    ) -> Result<H::Node, SynthesisError>
+    where
+        E: Engine,
+        CS: ConstraintSystem<E>,
+        H: MerkleHash<E>,
+    {
+        let mut cur = leaf;
+
+        for (depth, layer) in auth_path.into_iter().enumerate() {
+            let mut cs = cs.namespace(|| format!("layer {}", depth));
+
+            let cur_is_right = Boolean::from(AllocatedBit::alloc(
+                cs.namespace(|| "position bit"),
+                layer.as_ref().map(|&(_, p)| p),
+            )?);
+
+            let sibling = hash.alloc(cs.namespace(|| "sibling"), layer.map(|(s, _)| s))?;
+
+            cur = hash.hash_layer(cs.namespace(|| "hash"), depth, cur, sibling, &cur_is_right)?;
+        }
+
+        Ok(cur)
+    }
+}
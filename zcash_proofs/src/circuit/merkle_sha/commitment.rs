@@ -0,0 +1,46 @@
+//! The Sprout note commitment scheme.
+//!
+//! `cm = SHA256(0b10110000 || a_pk || value || rho || r)`. The leading
+//! byte tags the preimage as a Sprout note commitment, domain-separating
+//! it from the other uses of SHA256 in the JoinSplit statement.
+
+use bellman::gadgets::boolean::Boolean;
+use bellman::gadgets::sha256::sha256;
+use bellman::{ConstraintSystem, SynthesisError};
+use pairing::Engine;
+
+use super::NoteValue;
+
+pub fn note_comm<E, CS>(
+    mut cs: CS,
+    a_pk: &[Boolean],
+    value: &NoteValue,
+    rho: &[Boolean],
+    r: &[Boolean],
+) -> Result<Vec<Boolean>, SynthesisError>
+where
+    E: Engine,
+    CS: ConstraintSystem<E>,
+{
+    assert_eq!(a_pk.len(), 256);
+    assert_eq!(rho.len(), 256);
+    assert_eq!(r.len(), 256);
+
+    let mut preimage = vec![];
+
+    // 0b10110000, most significant bit first.
+    preimage.extend(
+        [true, false, true, true, false, false, false, false]
+            .iter()
+            .map(|&b| Boolean::constant(b)),
+    );
+    preimage.extend(a_pk.iter().cloned());
+    preimage.extend(value.bits_le());
+    preimage.extend(rho.iter().cloned());
+    preimage.extend(r.iter().cloned());
+
+    sha256(
+        cs.namespace(|| "SHA256(tag || a_pk || value || rho || r)"),
+        &preimage,
+    )
+}
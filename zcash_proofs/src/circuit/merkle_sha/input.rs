@@ -0,0 +1,100 @@
+//! Sprout input-note gadgets: deriving a spending key's address,
+//! recomputing a note's commitment and nullifier, and authenticating
+//! the commitment against the tree root.
+
+use bellman::gadgets::boolean::Boolean;
+use bellman::{ConstraintSystem, SynthesisError};
+use ff::Field;
+use pairing::Engine;
+
+use super::commitment::note_comm;
+use super::prfs::{prf_a_pk, prf_nf, prf_pk};
+use super::{
+    alloc_conditionally, alloc_value_is_zero, witness_u252, witness_u256, JSInput, NoteValue,
+    Sha256MerkleHash,
+};
+use super::super::merkle_hash::MerklePath;
+
+/// A fully witnessed input note, with its value (for the balance
+/// equation), nullifier and MAC (both exposed as public inputs) ready to
+/// use.
+pub struct InputNote {
+    pub value: NoteValue,
+    pub nf: Vec<Boolean>,
+    pub mac: Vec<Boolean>,
+}
+
+impl InputNote {
+    /// Witnesses `input`, recomputes its note commitment and nullifier,
+    /// and enforces that the commitment is a member of the tree rooted
+    /// at `rt`.
+    pub fn compute<E, CS>(
+        mut cs: CS,
+        input: JSInput,
+        rt: &[Boolean],
+        h_sig: &[Boolean],
+        i: bool,
+    ) -> Result<InputNote, SynthesisError>
+    where
+        E: Engine,
+        CS: ConstraintSystem<E>,
+    {
+        let a_sk = witness_u252(
+            cs.namespace(|| "a_sk"),
+            input.a_sk.as_ref().map(|s| &s.0[..]),
+        )?;
+
+        let a_pk = prf_a_pk(cs.namespace(|| "a_pk"), &a_sk)?;
+
+        let value = NoteValue::new(cs.namespace(|| "value"), input.value)?;
+
+        let rho = witness_u256(
+            cs.namespace(|| "rho"),
+            input.rho.as_ref().map(|r| &r.0[..]),
+        )?;
+        let r = witness_u256(cs.namespace(|| "r"), input.r.as_ref().map(|r| &r.0[..]))?;
+
+        let nf = prf_nf(cs.namespace(|| "nf"), &a_sk, &rho)?;
+        let mac = prf_pk(cs.namespace(|| "mac"), &a_sk, i, h_sig)?;
+
+        let cm = note_comm(cs.namespace(|| "note commitment"), &a_pk, &value, &rho, &r)?;
+
+        // Ascend the Merkle tree from `cm`, authenticating it against `rt`.
+        let cur = MerklePath::compute_root(
+            cs.namespace(|| "merkle path"),
+            &Sha256MerkleHash,
+            cm,
+            input.auth_path,
+        )?;
+
+        // Dummy (zero-value) notes don't exist in the tree, so their
+        // Merkle membership check must be skipped: `enforce_flag` is
+        // pinned to the exact negation of `value_is_zero`, so it is `1`
+        // for every real (nonzero-value) note and `0` for dummy notes --
+        // a prover cannot witness `enforce_flag = 0` for a real note to
+        // skip the membership check below.
+        let value_is_zero = alloc_value_is_zero(cs.namespace(|| "value is zero"), &value)?;
+        let enforce_flag = alloc_conditionally(
+            cs.namespace(|| "enforce membership"),
+            value.get_value().map(|v| v != 0),
+            &value_is_zero,
+        )?;
+        cs.enforce(
+            || "enforce_flag = 1 - value is zero",
+            |lc| lc + CS::one(),
+            |lc| lc + enforce_flag.get_variable() + value_is_zero.get_variable(),
+            |lc| lc + CS::one(),
+        );
+
+        for (i, (cur, rt)) in cur.iter().zip(rt.iter()).enumerate() {
+            cs.enforce(
+                || format!("enforce correct root for bit {}", i),
+                |_| cur.lc(CS::one(), E::Fr::one()) - &rt.lc(CS::one(), E::Fr::one()),
+                |lc| lc + enforce_flag.get_variable(),
+                |lc| lc,
+            );
+        }
+
+        Ok(InputNote { value, nf, mac })
+    }
+}
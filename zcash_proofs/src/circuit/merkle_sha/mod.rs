@@ -12,19 +12,59 @@
 
 use bellman::gadgets::boolean::{AllocatedBit, Boolean};
 use bellman::gadgets::multipack::pack_into_inputs;
-use bellman::{Circuit, ConstraintSystem, LinearCombination, SynthesisError};
 use bellman::gadgets::sha256::sha256_block_no_padding;
+use bellman::{Circuit, ConstraintSystem, LinearCombination, SynthesisError};
 use ff::Field;
 use pairing::Engine;
 
+use super::merkle_hash::MerkleHash;
+
 mod commitment;
 mod input;
 mod output;
 mod prfs;
 
+use self::input::InputNote;
+use self::output::OutputNote;
+
+/// The `MerkleHash` impl for the Sprout SHA256 tree: nodes are
+/// byte-oriented 256-bit hashes, and each layer's compression is
+/// SHA256 of the (conditionally swapped) pair of children, without
+/// padding.
+pub struct Sha256MerkleHash;
+
+impl<E: Engine> MerkleHash<E> for Sha256MerkleHash {
+    type Node = Vec<Boolean>;
+    type Repr = [u8; 32];
+
+    fn alloc<CS>(&self, cs: CS, value: Option<Self::Repr>) -> Result<Self::Node, SynthesisError>
+    where
+        CS: ConstraintSystem<E>,
+    {
+        witness_u256(cs, value.as_ref().map(|v| &v[..]))
+    }
 
-use self::input::*;
-use self::output::*;
+    fn hash_layer<CS>(
+        &self,
+        mut cs: CS,
+        _depth: usize,
+        cur: Self::Node,
+        sibling: Self::Node,
+        cur_is_right: &Boolean,
+    ) -> Result<Self::Node, SynthesisError>
+    where
+        CS: ConstraintSystem<E>,
+    {
+        let preimage = conditionally_swap_u256(
+            cs.namespace(|| "conditional swap"),
+            &cur,
+            &sibling,
+            cur_is_right,
+        )?;
+
+        sha256_block_no_padding(cs.namespace(|| "hash of this layer"), &preimage)
+    }
+}
 
 pub struct NoteValue {
     value: Option<u64>,
@@ -93,84 +133,276 @@ impl NoteValue {
     }
 }
 
+#[derive(Clone)]
 pub struct SpendingKey(pub [u8; 32]);
+#[derive(Clone)]
 pub struct PayingKey(pub [u8; 32]);
+#[derive(Clone)]
 pub struct UniqueRandomness(pub [u8; 32]);
+#[derive(Clone)]
 pub struct CommitmentRandomness(pub [u8; 32]);
 
 #[derive(Clone)]
 pub struct JoinSplit {
     pub inputs: Vec<JSInput>,
+    pub outputs: Vec<JSOutput>,
     pub rt: Option<[u8; 32]>,
+    pub h_sig: Option<[u8; 32]>,
+    pub vpub_old: Option<u64>,
+    pub vpub_new: Option<u64>,
 }
 
 #[derive(Clone)]
 pub struct JSInput {
-    pub leaf: Option<[u8; 32]>,
+    pub value: Option<u64>,
+    pub a_sk: Option<SpendingKey>,
+    pub rho: Option<UniqueRandomness>,
+    pub r: Option<CommitmentRandomness>,
     pub auth_path: Vec<Option<([u8; 32], bool)>>,
 }
 
+#[derive(Clone)]
+pub struct JSOutput {
+    pub value: Option<u64>,
+    pub a_pk: Option<PayingKey>,
+    pub rho: Option<UniqueRandomness>,
+    pub r: Option<CommitmentRandomness>,
+}
+
 impl<E: Engine> Circuit<E> for JoinSplit {
     fn synthesize<CS: ConstraintSystem<E>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
-        assert_eq!(self.inputs.len(), 1);
+        // Sprout JoinSplits always have exactly two inputs and two
+        // outputs; zero-value notes are "dummy" and bypass the Merkle
+        // membership check (see `InputNote::compute`).
+        assert_eq!(self.inputs.len(), 2);
+        assert_eq!(self.outputs.len(), 2);
+
+        // Witness rt (merkle tree root) and h_sig (binding signature hash)
+        let rt = witness_u256(cs.namespace(|| "rt"), self.rt.as_ref().map(|v| &v[..]))?;
+        let h_sig = witness_u256(
+            cs.namespace(|| "h_sig"),
+            self.h_sig.as_ref().map(|v| &v[..]),
+        )?;
+
+        // Iterate over the JoinSplit inputs, recomputing each note's
+        // commitment, nullifier and MAC, and authenticating the
+        // commitment against `rt`.
+        let mut input_notes = vec![];
+        for (i, input) in self.inputs.into_iter().enumerate() {
+            let cs = cs.namespace(|| format!("input {}", i));
+
+            input_notes.push(InputNote::compute(cs, input, &rt, &h_sig, i != 0)?);
+        }
 
-        // Witness rt (merkle tree root)
-        let rt = witness_u256(cs.namespace(|| "rt"), self.rt.as_ref().map(|v| &v[..])).unwrap();
+        // Iterate over the JoinSplit outputs, recomputing each note's
+        // commitment.
+        let mut output_notes = vec![];
+        for (i, output) in self.outputs.into_iter().enumerate() {
+            let cs = cs.namespace(|| format!("output {}", i));
 
+            output_notes.push(OutputNote::compute(cs, output)?);
+        }
 
-        // Iterate over the JoinSplit inputs
-        for (i, input) in self.inputs.into_iter().enumerate() {
-            let cs = &mut cs.namespace(|| format!("input {}", i));
-            let leaf = witness_u256(cs.namespace(|| "leaf"), input.leaf.as_ref().map(|v| &v[..])).unwrap();
-
-            // Witness into the merkle tree
-            let mut cur = leaf.clone();
-
-            for (i, layer) in input.auth_path.iter().enumerate() {
-                let cs = &mut cs.namespace(|| format!("layer {}", i));
-
-                let cur_is_right = AllocatedBit::alloc(
-                    cs.namespace(|| "cur is right"),
-                    layer.as_ref().map(|&(_, p)| p),
-                )?;
-
-                let lhs = cur;
-                let rhs = witness_u256(
-                    cs.namespace(|| "sibling"),
-                    layer.as_ref().map(|&(ref sibling, _)| &sibling[..]),
-                )?;
-
-                // Conditionally swap if cur is right
-                let preimage = conditionally_swap_u256(
-                    cs.namespace(|| "conditional swap"),
-                    &lhs[..],
-                    &rhs[..],
-                    &cur_is_right,
-                )?;
-
-                cur = sha256_block_no_padding(cs.namespace(|| "hash of this layer"), &preimage)?;
+        let vpub_old = NoteValue::new(cs.namespace(|| "vpub_old"), self.vpub_old)?;
+        let vpub_new = NoteValue::new(cs.namespace(|| "vpub_new"), self.vpub_new)?;
+
+        // Enforce the Sprout balance equation:
+        //   vpub_old + sum(input values) = vpub_new + sum(output values)
+        // Each term is at most 64 bits and there are at most four notes
+        // plus the two public values, so neither side can exceed 66
+        // bits; this is far below the field's bit length, so the sum
+        // cannot wrap and the equation is sound as a single check.
+        {
+            let mut lhs = vpub_old.lc::<E>();
+            for note in &input_notes {
+                lhs = lhs + &note.value.lc::<E>();
             }
 
-            /*
-            for (i, (cur, rt)) in cur.into_iter().zip(rt.iter()).enumerate() {
-                cs.enforce(
-                    || format!("conditionally enforce correct root for bit {}", i),
-                    |_| cur.lc(CS::one(), E::Fr::one()),
-                    |_| rt.lc(CS::one(), E::Fr::one()),
-                    |lc| lc,
-                );
+            let mut rhs = vpub_new.lc::<E>();
+            for note in &output_notes {
+                rhs = rhs + &note.value.lc::<E>();
             }
-            */
-        }
 
+            cs.enforce(
+                || "balance equation",
+                |lc| lc + CS::one(),
+                |_| lhs,
+                |_| rhs,
+            );
+        }
 
         let mut public_inputs = vec![];
         public_inputs.extend(rt);
+        public_inputs.extend(h_sig);
+        for note in &input_notes {
+            public_inputs.extend(note.nf.clone());
+        }
+        for note in &input_notes {
+            public_inputs.extend(note.mac.clone());
+        }
+        for note in &output_notes {
+            public_inputs.extend(note.cm.clone());
+        }
+        public_inputs.extend(vpub_old.bits_le());
+        public_inputs.extend(vpub_new.bits_le());
 
         pack_into_inputs(cs.namespace(|| "input packing"), &public_inputs)
     }
 }
 
+/// Allocates a boolean that is forced to `false` when `must_be_false` is
+/// true; otherwise it is an ordinary witnessed boolean.
+///
+/// `(1 - must_be_false - a) * a = 0` reduces to the standard boolean
+/// constraint `(1 - a) * a = 0` when `must_be_false` is false, and to
+/// `a = 0` when it is true.
+fn alloc_conditionally<E, CS>(
+    mut cs: CS,
+    value: Option<bool>,
+    must_be_false: &AllocatedBit,
+) -> Result<AllocatedBit, SynthesisError>
+where
+    E: Engine,
+    CS: ConstraintSystem<E>,
+{
+    let a = AllocatedBit::alloc(cs.namespace(|| "alloc conditionally"), value)?;
+
+    cs.enforce(
+        || "(1 - must_be_false - a) * a = 0",
+        |lc| lc + CS::one() - must_be_false.get_variable() - a.get_variable(),
+        |lc| lc + a.get_variable(),
+        |lc| lc,
+    );
+
+    Ok(a)
+}
+
+/// Returns a boolean that is constrained to `1` iff `value`'s bits sum
+/// to zero, using the standard `value * inv = 1 - is_zero`,
+/// `value * is_zero = 0` trick.
+fn alloc_value_is_zero<E, CS>(mut cs: CS, value: &NoteValue) -> Result<AllocatedBit, SynthesisError>
+where
+    E: Engine,
+    CS: ConstraintSystem<E>,
+{
+    let is_zero = AllocatedBit::alloc(
+        cs.namespace(|| "value is zero"),
+        value.get_value().map(|v| v == 0),
+    )?;
+
+    let value_lc = value.lc::<E>();
+
+    let inv = cs.alloc(
+        || "inverse of value",
+        || {
+            let value = value.get_value().ok_or(SynthesisError::AssignmentMissing)?;
+
+            Ok(if value == 0 {
+                E::Fr::zero()
+            } else {
+                let mut tmp = E::Fr::zero();
+                let mut coeff = E::Fr::one();
+                for i in 0..64 {
+                    if (value >> i) & 1 == 1 {
+                        tmp.add_assign(&coeff);
+                    }
+                    coeff.double();
+                }
+                tmp.inverse().expect("value is nonzero")
+            })
+        },
+    )?;
+
+    // value * inv = 1 - is_zero
+    cs.enforce(
+        || "value * inv = 1 - is_zero",
+        |_| value_lc.clone(),
+        |lc| lc + inv,
+        |lc| lc + CS::one() - is_zero.get_variable(),
+    );
+
+    // value * is_zero = 0
+    cs.enforce(
+        || "value * is_zero = 0",
+        |_| value.lc::<E>(),
+        |lc| lc + is_zero.get_variable(),
+        |lc| lc,
+    );
+
+    Ok(is_zero)
+}
+
+/// If `condition` is true, returns `(rhs, lhs)`; otherwise `(lhs, rhs)`,
+/// concatenated into a single 512-bit preimage.
+fn conditionally_swap_u256<E, CS>(
+    mut cs: CS,
+    lhs: &[Boolean],
+    rhs: &[Boolean],
+    condition: &Boolean,
+) -> Result<Vec<Boolean>, SynthesisError>
+where
+    E: Engine,
+    CS: ConstraintSystem<E>,
+{
+    assert_eq!(lhs.len(), 256);
+    assert_eq!(rhs.len(), 256);
+
+    let mut new_lhs = vec![];
+    let mut new_rhs = vec![];
+
+    for (i, (lhs, rhs)) in lhs.iter().zip(rhs.iter()).enumerate() {
+        let cs = &mut cs.namespace(|| format!("bit {}", i));
+
+        let x = Boolean::from(AllocatedBit::alloc(
+            cs.namespace(|| "x"),
+            condition.get_value().and_then(|v| {
+                if v {
+                    rhs.get_value()
+                } else {
+                    lhs.get_value()
+                }
+            }),
+        )?);
+
+        // x = lhs + condition * (rhs - lhs)
+        cs.enforce(
+            || "x is correctly computed",
+            |_| rhs.lc(CS::one(), E::Fr::one()) - &lhs.lc(CS::one(), E::Fr::one()),
+            |_| condition.lc(CS::one(), E::Fr::one()),
+            |_| x.lc(CS::one(), E::Fr::one()) - &lhs.lc(CS::one(), E::Fr::one()),
+        );
+
+        new_lhs.push(x);
+
+        let y = Boolean::from(AllocatedBit::alloc(
+            cs.namespace(|| "y"),
+            condition.get_value().and_then(|v| {
+                if v {
+                    lhs.get_value()
+                } else {
+                    rhs.get_value()
+                }
+            }),
+        )?);
+
+        // y = rhs + condition * (lhs - rhs)
+        cs.enforce(
+            || "y is correctly computed",
+            |_| lhs.lc(CS::one(), E::Fr::one()) - &rhs.lc(CS::one(), E::Fr::one()),
+            |_| condition.lc(CS::one(), E::Fr::one()),
+            |_| y.lc(CS::one(), E::Fr::one()) - &rhs.lc(CS::one(), E::Fr::one()),
+        );
+
+        new_rhs.push(y);
+    }
+
+    let mut preimage = vec![];
+    preimage.extend(new_lhs);
+    preimage.extend(new_rhs);
+
+    Ok(preimage)
+}
+
 /// Witnesses some bytes in the constraint system,
 /// skipping the first `skip_bits`.
 fn witness_bits<E, CS>(
@@ -226,93 +458,231 @@ where
     witness_bits(cs, value, 252, 4)
 }
 
-#[test]
-fn test_sprout_constraints() {
-    use bellman::gadgets::test::*;
-    use pairing::bls12_381::Bls12;
-
-    use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
-
-    let test_vector = include_bytes!("test_vectors.dat");
-    let mut test_vector = &test_vector[..];
-
-    fn get_u256<R: ReadBytesExt>(mut reader: R) -> [u8; 32] {
-        let mut result = [0u8; 32];
-
-        for i in 0..32 {
-            result[i] = reader.read_u8().unwrap();
+#[cfg(test)]
+mod test_helpers {
+    //! Host-side mirrors of the PRF, note-commitment and Merkle-layer
+    //! gadgets in this module, used by `test_sprout_constraints` to build
+    //! a witness the circuit should actually accept (and to cross-check
+    //! the circuit's recomputed `nf`/`mac`/`cm` against it), rather than
+    //! relying on `synthesize` returning `Ok` alone.
+
+    use sha2::digest::generic_array::GenericArray;
+    use sha2::{compress256, Digest, Sha256};
+
+    /// The raw (unpadded) SHA256 compression function applied to a single
+    /// 512-bit block, matching `sha256_block_no_padding`.
+    pub fn compress(block: &[u8; 64]) -> [u8; 32] {
+        let mut state = [
+            0x6a09_e667u32,
+            0xbb67_ae85,
+            0x3c6e_f372,
+            0xa54f_f53a,
+            0x510e_527f,
+            0x9b05_688c,
+            0x1f83_d9ab,
+            0x5be0_cd19,
+        ];
+        compress256(&mut state, &[GenericArray::clone_from_slice(block)]);
+
+        let mut out = [0u8; 32];
+        for (word, chunk) in state.iter().zip(out.chunks_mut(4)) {
+            chunk.copy_from_slice(&word.to_be_bytes());
         }
+        out
+    }
 
-        result
+    /// MSB-first-per-byte bit serialization, matching `witness_bits`.
+    pub fn bytes_to_bits(bytes: &[u8]) -> Vec<bool> {
+        bytes
+            .iter()
+            .flat_map(|&b| (0..8).rev().map(move |i| (b >> i) & 1 == 1))
+            .collect()
     }
 
-    while test_vector.len() != 0 {
-        let mut cs = TestConstraintSystem::<Bls12>::new();
+    fn bits_to_bytes(bits: &[bool]) -> Vec<u8> {
+        bits.chunks(8)
+            .map(|chunk| chunk.iter().fold(0u8, |acc, &b| (acc << 1) | (b as u8)))
+            .collect()
+    }
 
-        let phi = Some(get_u256(&mut test_vector));
-        let rt = Some(get_u256(&mut test_vector));
-        let h_sig = Some(get_u256(&mut test_vector));
+    /// Mirrors `prfs::prf`: one SHA256 compression over a single 512-bit
+    /// block made up of 4 discriminant bits, a 252-bit `x` and a 256-bit
+    /// `y`.
+    pub fn prf(a: bool, b: bool, c: bool, d: bool, x: &[bool], y: &[bool]) -> [u8; 32] {
+        assert_eq!(x.len(), 252);
+        assert_eq!(y.len(), 256);
 
-        const TREE_DEPTH: usize = 29;
+        let mut bits = vec![a, b, c, d];
+        bits.extend_from_slice(x);
+        bits.extend_from_slice(y);
 
-        let mut inputs = vec![];
-        for i in 0..2 {
-            test_vector.read_u8().unwrap();
+        let mut block = [0u8; 64];
+        block.copy_from_slice(&bits_to_bytes(&bits));
+        compress(&block)
+    }
 
-            let mut auth_path = vec![None; TREE_DEPTH];
-            for i in (0..TREE_DEPTH).rev() {
-                test_vector.read_u8().unwrap();
+    fn a_sk_bits(a_sk: &[u8; 32]) -> Vec<bool> {
+        bytes_to_bits(a_sk)[4..].to_vec()
+    }
 
-                let sibling = get_u256(&mut test_vector);
+    pub fn prf_a_pk(a_sk: &[u8; 32]) -> [u8; 32] {
+        prf(true, false, false, false, &a_sk_bits(a_sk), &[false; 256])
+    }
 
-                auth_path[i] = Some((sibling, false));
-            }
-            let mut position = test_vector.read_u64::<LittleEndian>().unwrap();
-            for i in 0..TREE_DEPTH {
-                auth_path[i].as_mut().map(|p| p.1 = (position & 1) == 1);
+    pub fn prf_nf(a_sk: &[u8; 32], rho: &[u8; 32]) -> [u8; 32] {
+        prf(true, true, false, false, &a_sk_bits(a_sk), &bytes_to_bits(rho))
+    }
 
-                position >>= 1;
-            }
+    pub fn prf_pk(a_sk: &[u8; 32], i: bool, h_sig: &[u8; 32]) -> [u8; 32] {
+        prf(false, i, true, false, &a_sk_bits(a_sk), &bytes_to_bits(h_sig))
+    }
 
-            // a_pk
-            let _ = Some(SpendingKey(get_u256(&mut test_vector)));
-            let value = Some(test_vector.read_u64::<LittleEndian>().unwrap());
-            let rho = Some(UniqueRandomness(get_u256(&mut test_vector)));
-            let r = Some(CommitmentRandomness(get_u256(&mut test_vector)));
-            let leaf = Some(get_u256(&mut test_vector));
-
-            if i == 0 {
-                inputs.push(JSInput {
-                    leaf,
-                    auth_path,
-                });
-            }
-        }
+    /// Mirrors `commitment::note_comm`: a padded SHA256 of
+    /// `0b10110000 || a_pk || value (LE bytes) || rho || r`.
+    pub fn note_comm(a_pk: &[u8; 32], value: u64, rho: &[u8; 32], r: &[u8; 32]) -> [u8; 32] {
+        let mut bits = vec![true, false, true, true, false, false, false, false];
+        bits.extend(bytes_to_bits(a_pk));
+        bits.extend(bytes_to_bits(&value.to_le_bytes()));
+        bits.extend(bytes_to_bits(rho));
+        bits.extend(bytes_to_bits(r));
+
+        let mut out = [0u8; 32];
+        out.copy_from_slice(&Sha256::digest(&bits_to_bytes(&bits)));
+        out
+    }
 
-        for _ in 0..2 {
-            let a_pk = Some(PayingKey(get_u256(&mut test_vector)));
-            let value = Some(test_vector.read_u64::<LittleEndian>().unwrap());
-            get_u256(&mut test_vector);
-            let r = Some(CommitmentRandomness(get_u256(&mut test_vector)));
+    /// Mirrors `Sha256MerkleHash::hash_layer` (via `conditionally_swap_u256`):
+    /// hashes the (sibling, cur) pair in tree order.
+    pub fn merkle_hash_layer(cur: [u8; 32], sibling: [u8; 32], cur_is_right: bool) -> [u8; 32] {
+        let mut block = [0u8; 64];
+        if cur_is_right {
+            block[..32].copy_from_slice(&sibling);
+            block[32..].copy_from_slice(&cur);
+        } else {
+            block[..32].copy_from_slice(&cur);
+            block[32..].copy_from_slice(&sibling);
         }
+        compress(&block)
+    }
+}
+
+#[test]
+fn test_sprout_constraints() {
+    use bellman::gadgets::multipack;
+    use bellman::gadgets::test::*;
+    use pairing::bls12_381::Bls12;
 
-        let vpub_old = Some(test_vector.read_u64::<LittleEndian>().unwrap());
-        let vpub_new = Some(test_vector.read_u64::<LittleEndian>().unwrap());
+    use self::test_helpers::*;
 
-        let nf1 = get_u256(&mut test_vector);
-        let nf2 = get_u256(&mut test_vector);
+    const TREE_DEPTH: usize = 3;
 
-        let cm1 = get_u256(&mut test_vector);
-        let cm2 = get_u256(&mut test_vector);
+    // One real (nonzero-value) input note, authenticated by a genuine
+    // Merkle path, and one dummy (zero-value) input note whose path is
+    // garbage -- its membership check must be (and is) skipped.
+    let a_sk1 = [1u8; 32];
+    let rho1 = [2u8; 32];
+    let r1 = [3u8; 32];
+    let value1 = 10u64;
 
-        let mac1 = get_u256(&mut test_vector);
-        let mac2 = get_u256(&mut test_vector);
+    let a_pk1 = prf_a_pk(&a_sk1);
+    let cm1 = note_comm(&a_pk1, value1, &rho1, &r1);
 
-        let js = JoinSplit {
-            inputs,
-            rt,
-        };
+    let siblings: Vec<[u8; 32]> = (0..TREE_DEPTH).map(|i| [20 + i as u8; 32]).collect();
+    let positions = [false, true, false];
 
-        js.synthesize(&mut cs).unwrap();
+    let mut rt = cm1;
+    for (sibling, &right) in siblings.iter().zip(positions.iter()) {
+        rt = merkle_hash_layer(rt, *sibling, right);
     }
+
+    let auth_path1: Vec<Option<([u8; 32], bool)>> = siblings
+        .iter()
+        .zip(positions.iter())
+        .map(|(s, &right)| Some((*s, right)))
+        .collect();
+
+    let a_sk2 = [4u8; 32];
+    let rho2 = [5u8; 32];
+    let r2 = [6u8; 32];
+    let value2 = 0u64;
+    let auth_path2: Vec<Option<([u8; 32], bool)>> = vec![Some(([0u8; 32], false)); TREE_DEPTH];
+
+    let h_sig = [7u8; 32];
+
+    let nf1 = prf_nf(&a_sk1, &rho1);
+    let mac1 = prf_pk(&a_sk1, false, &h_sig);
+    let nf2 = prf_nf(&a_sk2, &rho2);
+    let mac2 = prf_pk(&a_sk2, true, &h_sig);
+
+    // Two outputs whose values balance the single real input.
+    let a_pk_out1 = [8u8; 32];
+    let rho_out1 = [9u8; 32];
+    let r_out1 = [11u8; 32];
+    let value_out1 = 6u64;
+    let cm_out1 = note_comm(&a_pk_out1, value_out1, &rho_out1, &r_out1);
+
+    let a_pk_out2 = [12u8; 32];
+    let rho_out2 = [13u8; 32];
+    let r_out2 = [14u8; 32];
+    let value_out2 = 4u64;
+    let cm_out2 = note_comm(&a_pk_out2, value_out2, &rho_out2, &r_out2);
+
+    let js = JoinSplit {
+        inputs: vec![
+            JSInput {
+                value: Some(value1),
+                a_sk: Some(SpendingKey(a_sk1)),
+                rho: Some(UniqueRandomness(rho1)),
+                r: Some(CommitmentRandomness(r1)),
+                auth_path: auth_path1,
+            },
+            JSInput {
+                value: Some(value2),
+                a_sk: Some(SpendingKey(a_sk2)),
+                rho: Some(UniqueRandomness(rho2)),
+                r: Some(CommitmentRandomness(r2)),
+                auth_path: auth_path2,
+            },
+        ],
+        outputs: vec![
+            JSOutput {
+                value: Some(value_out1),
+                a_pk: Some(PayingKey(a_pk_out1)),
+                rho: Some(UniqueRandomness(rho_out1)),
+                r: Some(CommitmentRandomness(r_out1)),
+            },
+            JSOutput {
+                value: Some(value_out2),
+                a_pk: Some(PayingKey(a_pk_out2)),
+                rho: Some(UniqueRandomness(rho_out2)),
+                r: Some(CommitmentRandomness(r_out2)),
+            },
+        ],
+        rt: Some(rt),
+        h_sig: Some(h_sig),
+        vpub_old: Some(0),
+        vpub_new: Some(0),
+    };
+
+    let mut cs = TestConstraintSystem::<Bls12>::new();
+    js.synthesize(&mut cs).unwrap();
+
+    assert!(cs.is_satisfied());
+
+    // Cross-check the circuit's recomputed nf/mac/cm against the
+    // host-side values, in the same order `synthesize` packs them.
+    let mut public_bits = vec![];
+    public_bits.extend(bytes_to_bits(&rt));
+    public_bits.extend(bytes_to_bits(&h_sig));
+    public_bits.extend(bytes_to_bits(&nf1));
+    public_bits.extend(bytes_to_bits(&nf2));
+    public_bits.extend(bytes_to_bits(&mac1));
+    public_bits.extend(bytes_to_bits(&mac2));
+    public_bits.extend(bytes_to_bits(&cm_out1));
+    public_bits.extend(bytes_to_bits(&cm_out2));
+    public_bits.extend(bytes_to_bits(&0u64.to_le_bytes()));
+    public_bits.extend(bytes_to_bits(&0u64.to_le_bytes()));
+
+    let expected_inputs = multipack::compute_multipacking::<Bls12>(&public_bits);
+    assert!(cs.verify(&expected_inputs));
 }
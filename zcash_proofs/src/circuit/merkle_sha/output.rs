@@ -0,0 +1,42 @@
+//! Sprout output-note gadgets: witnessing a new note and recomputing
+//! its commitment.
+
+use bellman::gadgets::boolean::Boolean;
+use bellman::{ConstraintSystem, SynthesisError};
+use pairing::Engine;
+
+use super::commitment::note_comm;
+use super::{witness_u256, JSOutput, NoteValue};
+
+/// A fully witnessed output note, with its value (for the balance
+/// equation) and commitment (exposed as a public input) ready to use.
+pub struct OutputNote {
+    pub value: NoteValue,
+    pub cm: Vec<Boolean>,
+}
+
+impl OutputNote {
+    /// Witnesses `output` and recomputes its note commitment.
+    pub fn compute<E, CS>(mut cs: CS, output: JSOutput) -> Result<OutputNote, SynthesisError>
+    where
+        E: Engine,
+        CS: ConstraintSystem<E>,
+    {
+        let a_pk = witness_u256(
+            cs.namespace(|| "a_pk"),
+            output.a_pk.as_ref().map(|a| &a.0[..]),
+        )?;
+
+        let value = NoteValue::new(cs.namespace(|| "value"), output.value)?;
+
+        let rho = witness_u256(
+            cs.namespace(|| "rho"),
+            output.rho.as_ref().map(|r| &r.0[..]),
+        )?;
+        let r = witness_u256(cs.namespace(|| "r"), output.r.as_ref().map(|r| &r.0[..]))?;
+
+        let cm = note_comm(cs.namespace(|| "note commitment"), &a_pk, &value, &rho, &r)?;
+
+        Ok(OutputNote { value, cm })
+    }
+}
@@ -0,0 +1,85 @@
+//! The Sprout pseudo-random functions.
+//!
+//! Each `PRF^x_{a_sk}(y)` is the SHA256 compression function applied to a
+//! single 512-bit block (no padding): four discriminant bits that select
+//! which PRF is being evaluated, the 252-bit spending key `a_sk`, and a
+//! 256-bit input `y`.
+//!
+//! [Concrete PRFs]: https://zips.z.cash/protocol/protocol.pdf#concreteprfs
+
+use bellman::gadgets::boolean::Boolean;
+use bellman::gadgets::sha256::sha256_block_no_padding;
+use bellman::{ConstraintSystem, SynthesisError};
+use pairing::Engine;
+
+fn prf<E, CS>(
+    mut cs: CS,
+    a: bool,
+    b: bool,
+    c: bool,
+    d: bool,
+    x: &[Boolean],
+    y: &[Boolean],
+) -> Result<Vec<Boolean>, SynthesisError>
+where
+    E: Engine,
+    CS: ConstraintSystem<E>,
+{
+    assert_eq!(x.len(), 252);
+    assert_eq!(y.len(), 256);
+
+    let mut block = vec![
+        Boolean::constant(a),
+        Boolean::constant(b),
+        Boolean::constant(c),
+        Boolean::constant(d),
+    ];
+    block.extend(x.iter().cloned());
+    block.extend(y.iter().cloned());
+
+    sha256_block_no_padding(cs.namespace(|| "PRF"), &block)
+}
+
+/// `a_pk = PRF_addr(a_sk) = PRF[1,0,0,0]_{a_sk}(0)`
+pub fn prf_a_pk<E, CS>(cs: CS, a_sk: &[Boolean]) -> Result<Vec<Boolean>, SynthesisError>
+where
+    E: Engine,
+    CS: ConstraintSystem<E>,
+{
+    prf(
+        cs,
+        true,
+        false,
+        false,
+        false,
+        a_sk,
+        &vec![Boolean::constant(false); 256],
+    )
+}
+
+/// `nf = PRF_nf(a_sk, rho) = PRF[1,1,0,0]_{a_sk}(rho)`
+pub fn prf_nf<E, CS>(
+    cs: CS,
+    a_sk: &[Boolean],
+    rho: &[Boolean],
+) -> Result<Vec<Boolean>, SynthesisError>
+where
+    E: Engine,
+    CS: ConstraintSystem<E>,
+{
+    prf(cs, true, true, false, false, a_sk, rho)
+}
+
+/// `mac = PRF_pk(a_sk, i, h_sig) = PRF[0,i,1,0]_{a_sk}(h_sig)`
+pub fn prf_pk<E, CS>(
+    cs: CS,
+    a_sk: &[Boolean],
+    i: bool,
+    h_sig: &[Boolean],
+) -> Result<Vec<Boolean>, SynthesisError>
+where
+    E: Engine,
+    CS: ConstraintSystem<E>,
+{
+    prf(cs, false, i, true, false, a_sk, h_sig)
+}
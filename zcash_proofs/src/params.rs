@@ -0,0 +1,100 @@
+//! Utilities for measuring and persisting Groth16 `Parameters`.
+//!
+//! `generate_random_parameters` is expensive, and benchmarks that
+//! regenerate the CRS on every run tend to accumulate ad-hoc,
+//! easily-stale byte-counting code alongside it. `serialized_size`
+//! computes the exact size of the encoded points of a `Parameters<Bls12>`
+//! from the curve's own point encodings, split between the verifying key (what
+//! on-chain verification has to pay for) and the proving key; `write`
+//! and `read` persist the CRS to disk so it only has to be generated
+//! once.
+
+use std::io::{self, Read, Write};
+
+use bellman::groth16::Parameters;
+use pairing::bls12_381::Bls12;
+use pairing::{CurveAffine, EncodedPoint, Engine};
+
+fn uncompressed_size<G: CurveAffine>() -> usize {
+    <G::Uncompressed as EncodedPoint>::size()
+}
+
+/// Returns the exact `(verifying key bytes, proving key bytes)` size of
+/// the encoded points of `params`, using the curve's uncompressed point
+/// encoding -- `Parameters::write` serializes every CRS point with
+/// `into_uncompressed()` (compressed encoding is only ever used for the
+/// final `Proof`), so this must match that, not the compressed size.
+/// The 4-byte length prefix that `Parameters::write` puts before each of
+/// its six point vectors is not counted.
+pub fn serialized_size(params: &Parameters<Bls12>) -> (usize, usize) {
+    let g1 = uncompressed_size::<<Bls12 as Engine>::G1Affine>();
+    let g2 = uncompressed_size::<<Bls12 as Engine>::G2Affine>();
+
+    let vk = &params.vk;
+    let vk_bytes = g1 * 3 // alpha_g1, beta_g1, delta_g1
+        + g2 * 3 // beta_g2, gamma_g2, delta_g2
+        + g1 * vk.ic.len();
+
+    let pk_bytes = g1 * params.a.len()
+        + g1 * params.b_g1.len()
+        + g2 * params.b_g2.len()
+        + g1 * params.h.len()
+        + g1 * params.l.len();
+
+    (vk_bytes, pk_bytes)
+}
+
+/// Serializes `params` to `writer`, so a CRS generated once can be
+/// reused across benchmark runs instead of being regenerated each time.
+pub fn write(params: &Parameters<Bls12>, writer: impl Write) -> io::Result<()> {
+    params.write(writer)
+}
+
+/// Reads back a CRS previously persisted with [`write`].
+pub fn read(reader: impl Read) -> io::Result<Parameters<Bls12>> {
+    Parameters::read(reader, true)
+}
+
+#[test]
+fn serialized_size_matches_a_real_write() {
+    use bellman::groth16::generate_random_parameters;
+    use bellman::{Circuit, ConstraintSystem, SynthesisError};
+    use ff::Field;
+    use pairing::bls12_381::Fr;
+    use rand_core::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    struct Trivial;
+
+    impl Circuit<Bls12> for Trivial {
+        fn synthesize<CS: ConstraintSystem<Bls12>>(
+            self,
+            cs: &mut CS,
+        ) -> Result<(), SynthesisError> {
+            let a = cs.alloc(|| "a", || Ok(Fr::one()))?;
+            let b = cs.alloc_input(|| "b", || Ok(Fr::one()))?;
+
+            cs.enforce(|| "a = b", |lc| lc + a, |lc| lc + CS::one(), |lc| lc + b);
+
+            Ok(())
+        }
+    }
+
+    let rng = &mut XorShiftRng::from_seed([
+        0x59, 0x62, 0xbe, 0x3d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
+        0xe5,
+    ]);
+
+    let params = generate_random_parameters::<Bls12, _, _>(Trivial, rng).unwrap();
+
+    let (vk_bytes, pk_bytes) = serialized_size(&params);
+
+    let mut buf = vec![];
+    write(&params, &mut buf).unwrap();
+
+    // `vk.ic`, `h`, `l`, `a`, `b_g1` and `b_g2` are each preceded by a
+    // 4-byte length prefix that `serialized_size` doesn't count, since
+    // it isn't part of the point data itself.
+    const NUM_LENGTH_PREFIXES: usize = 6;
+    assert_eq!(buf.len(), vk_bytes + pk_bytes + NUM_LENGTH_PREFIXES * 4);
+}